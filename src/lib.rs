@@ -1,2 +1,5 @@
-mod mp3;
+// Explicit `return` statements are the preferred style throughout this crate.
+#![allow(clippy::needless_return)]
+
+pub mod mp3;
 mod jpeg;
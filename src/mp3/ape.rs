@@ -0,0 +1,278 @@
+// The APEv2 tag layout is described at the link below.
+// https://wiki.hydrogenaud.io/index.php?title=APEv2_specification
+const PREAMBLE: &[u8; 8] = b"APETAGEX";
+pub(crate) const PREAMBLE_START: u8 = PREAMBLE[0];
+
+// These constants are the bits of the tag flags field.
+const HAS_HEADER: u32 =     0x80_00_00_00; // The tag contains a header
+const IS_HEADER: u32 =      0x20_00_00_00; // This is the header rather than the footer
+
+// These constants are the bits of the item flags field.
+const ITEM_TYPE: u32 =      0x00_00_00_06; // How the item value should be interpreted
+
+// The number of bytes in an APEv2 header or footer.
+pub(crate) const APE_FOOTER_LEN: usize = 32;
+
+/// How the value of an APEv2 item should be interpreted
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ApeItemKind
+{
+    Text,       // UTF-8 text (00)
+    Binary,     // Binary data (01)
+    Locator,    // UTF-8 link to external information (10)
+    Reserved,   // Reserved value (11)
+}
+
+/// A single key/value item of an APEv2 tag
+#[derive(Clone, PartialEq, Debug)]
+pub struct ApeItem
+{
+    key: String,
+    value: Vec<u8>,
+    kind: ApeItemKind,
+}
+
+impl ApeItem
+{
+    /// Returns the key of the item.
+    pub fn key(&self) -> &str
+    {
+        return &self.key;
+    }
+
+    /// Returns the raw value of the item.
+    pub fn value(&self) -> &[u8]
+    {
+        return &self.value;
+    }
+
+    /// Returns how the value of the item should be interpreted.
+    pub fn kind(&self) -> ApeItemKind
+    {
+        return self.kind;
+    }
+
+    /// Returns the value of a text or locator item, or None if the item is binary or isn't valid UTF-8.
+    pub fn text(&self) -> Option<&str>
+    {
+        return match self.kind
+        {
+            ApeItemKind::Text | ApeItemKind::Locator => std::str::from_utf8(&self.value).ok(),
+            _ => None,
+        };
+    }
+}
+
+/// An APEv2 tag, as appended to the end of a file by Monkey's Audio and other tools
+#[derive(Clone, PartialEq, Debug)]
+pub struct ApeTag
+{
+    version: u32,       // 2000 for APEv2, 1000 for APEv1
+    len: u32,           // Number of bytes in the tag, including the header and footer
+    items: Vec<ApeItem>,
+}
+
+impl ApeTag
+{
+    /// Returns the version of the tag, which is 2000 for APEv2.
+    pub fn version(&self) -> u32
+    {
+        return self.version;
+    }
+
+    /// Returns the number of bytes the tag occupies, including its header and footer.
+    pub fn len(&self) -> u32
+    {
+        return self.len;
+    }
+
+    /// Returns true if the tag doesn't contain any items.
+    pub fn is_empty(&self) -> bool
+    {
+        return self.items.is_empty();
+    }
+
+    /// Returns the items of the tag.
+    pub fn items(&self) -> &[ApeItem]
+    {
+        return &self.items;
+    }
+
+    /// Returns the item with the given key. Keys are compared ignoring ASCII case.
+    pub fn get(&self, key: &str) -> Option<&ApeItem>
+    {
+        return self.items.iter().find(|item| item.key.eq_ignore_ascii_case(key));
+    }
+}
+
+// Reads a little-endian u32 at `pos`, or None if the slice is too short.
+fn read_u32(data: &[u8], pos: usize) -> Option<u32>
+{
+    let bytes = data.get(pos..pos + 4)?;
+    return Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+}
+
+// The fields of an APEv2 header or footer
+pub(crate) struct ApeFooter
+{
+    version: u32,
+    size: u32,      // Number of bytes of items and footer, excluding the header
+    item_count: u32,
+    flags: u32,
+}
+
+impl ApeFooter
+{
+    // Parses an APEv2 footer from the start of `bytes`.
+    pub(crate) fn parse(bytes: &[u8]) -> Option<ApeFooter>
+    {
+        if bytes.get(..PREAMBLE.len())? != PREAMBLE
+        {
+            return None;
+        }
+        let footer = ApeFooter {
+            version: read_u32(bytes, 8)?,
+            size: read_u32(bytes, 12)?,
+            item_count: read_u32(bytes, 16)?,
+            flags: read_u32(bytes, 20)?,
+        };
+        if (footer.size as usize) < APE_FOOTER_LEN
+        {
+            return None;
+        }
+        return Some(footer);
+    }
+
+    // Returns true if this is the header of the tag rather than the footer.
+    pub(crate) fn is_header(&self) -> bool
+    {
+        return self.flags & IS_HEADER != 0;
+    }
+
+    // Returns the number of bytes in the whole tag, including the header if there is one.
+    pub(crate) fn tag_len(&self) -> usize
+    {
+        if self.flags & HAS_HEADER != 0
+        {
+            return self.size as usize + APE_FOOTER_LEN;
+        }
+        return self.size as usize;
+    }
+}
+
+/// Parses an APEv2 tag from the end of `bytes`. The tag may be followed by a 128 byte ID3v1 tag.
+/// Returns None if `bytes` doesn't end with a well formed APEv2 tag.
+pub fn parse_apev2(bytes: &[u8]) -> Option<ApeTag>
+{
+    let mut end = bytes.len();
+    if end >= 128 && &bytes[end - 128..end - 125] == b"TAG"
+    {
+        end -= 128;
+    }
+    let footer = ApeFooter::parse(bytes.get(end.checked_sub(APE_FOOTER_LEN)?..end)?)?;
+    if footer.is_header()
+    {
+        return None;
+    }
+    // Items sit between the header (if any) and the footer.
+    let items_end = end - APE_FOOTER_LEN;
+    let items_start = end.checked_sub(footer.size as usize)?;
+    let data = &bytes[items_start..items_end];
+
+    let mut items = Vec::with_capacity((footer.item_count as usize).min(data.len() / 9));
+    let mut pos = 0;
+    for _ in 0..footer.item_count
+    {
+        let value_len = read_u32(data, pos)? as usize;
+        let flags = read_u32(data, pos + 4)?;
+        let key_start = pos + 8;
+        let key_len = data.get(key_start..)?.iter().position(|&byte| byte == 0)?;
+        let key = std::str::from_utf8(&data[key_start..key_start + key_len]).ok()?.to_string();
+        let value_start = key_start + key_len + 1;
+        let value = data.get(value_start..value_start.checked_add(value_len)?)?.to_vec();
+        let kind = match (flags & ITEM_TYPE) >> 1
+        {
+            0b00 => ApeItemKind::Text,
+            0b01 => ApeItemKind::Binary,
+            0b10 => ApeItemKind::Locator,
+            _ => ApeItemKind::Reserved,
+        };
+        items.push(ApeItem { key, value, kind });
+        pos = value_start + value_len;
+    }
+    return Some(ApeTag { version: footer.version, len: footer.tag_len() as u32, items });
+}
+
+#[cfg(test)]
+pub(crate) mod tests
+{
+    use super::*;
+
+    // Returns the bytes of an APEv2 tag with a header, a footer, and the given text items.
+    pub(crate) fn ape_tag_bytes(items: &[(&str, &str)]) -> Vec<u8>
+    {
+        let mut data = Vec::new();
+        for (key, value) in items
+        {
+            data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            data.extend_from_slice(&0u32.to_le_bytes());
+            data.extend_from_slice(key.as_bytes());
+            data.push(0);
+            data.extend_from_slice(value.as_bytes());
+        }
+        let size = (data.len() + APE_FOOTER_LEN) as u32;
+        let block = |flags: u32| {
+            let mut block = PREAMBLE.to_vec();
+            block.extend_from_slice(&2000u32.to_le_bytes());
+            block.extend_from_slice(&size.to_le_bytes());
+            block.extend_from_slice(&(items.len() as u32).to_le_bytes());
+            block.extend_from_slice(&flags.to_le_bytes());
+            block.extend_from_slice(&[0; 8]);
+            block
+        };
+        let mut bytes = block(HAS_HEADER | IS_HEADER);
+        bytes.extend(data);
+        bytes.extend(block(HAS_HEADER));
+        return bytes;
+    }
+
+    /// Verifies that parse_apev2() reads the items of a tag.
+    #[test]
+    fn test_parse_apev2()
+    {
+        let bytes = ape_tag_bytes(&[("Title", "Song"), ("Artist", "Band")]);
+        let tag = parse_apev2(&bytes).unwrap();
+        assert_eq!(tag.version(), 2000);
+        assert_eq!(tag.len() as usize, bytes.len());
+        assert_eq!(tag.items().len(), 2);
+        assert_eq!(tag.items()[0].key(), "Title");
+        assert_eq!(tag.items()[0].kind(), ApeItemKind::Text);
+        assert_eq!(tag.get("artist").unwrap().text(), Some("Band"));
+        assert_eq!(tag.get("Album"), None);
+    }
+
+    /// Verifies that parse_apev2() finds a tag followed by an ID3v1 tag.
+    #[test]
+    fn test_parse_apev2_before_id3v1()
+    {
+        let mut bytes = vec![0xFF; 10];
+        bytes.extend(ape_tag_bytes(&[("Year", "1999")]));
+        let mut id3v1 = b"TAG".to_vec();
+        id3v1.resize(128, 0);
+        bytes.extend(id3v1);
+        let tag = parse_apev2(&bytes).unwrap();
+        assert_eq!(tag.get("Year").unwrap().text(), Some("1999"));
+    }
+
+    /// Verifies that parse_apev2() rejects bytes without a footer and tags with bad item sizes.
+    #[test]
+    fn test_parse_apev2_invalid()
+    {
+        assert_eq!(parse_apev2(&[0; 64]), None);
+        assert_eq!(parse_apev2(b"APETAGEX"), None);
+
+        let mut bytes = ape_tag_bytes(&[("Title", "Song")]);
+        bytes[32] = 0xFF;
+        assert_eq!(parse_apev2(&bytes), None);
+    }
+}
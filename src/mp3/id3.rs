@@ -0,0 +1,394 @@
+use super::GaplessInfo;
+
+// The ID3v2 tag layout is described at the link below.
+// https://id3.org/id3v2.4.0-structure
+const ID3V2_MAGIC: &[u8; 3] = b"ID3";
+
+// These constants are the bits of the tag header flags field.
+const UNSYNCHRONISATION: u8 = 0x80; // A zero byte follows every 0xFF in the tag that could be mistaken for a sync
+const EXTENDED_HEADER: u8 = 0x40; // An extended header follows the tag header
+const FOOTER: u8 =          0x10; // A copy of the header follows the tag, in ID3v2.4 only
+
+// The description of the comment or user text frame in which iTunes stores gapless playback info.
+const ITUNSMPB: &str = "iTunSMPB";
+
+/// The number of bytes in an ID3v2 tag header.
+pub const ID3V2_HEADER_LEN: usize = 10;
+
+// The number of bytes in an ID3v1 tag, which is placed at the end of a file.
+pub(crate) const ID3V1_LEN: usize = 128;
+
+/// The header of an ID3v2 tag, which is placed at the start of a file
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Id3v2Header
+{
+    major_version: u8,  // 2, 3, or 4 for ID3v2.2, ID3v2.3, and ID3v2.4
+    revision: u8,
+    flags: u8,
+    size: u32,          // Number of bytes in the tag, excluding the header and footer
+}
+
+impl Id3v2Header
+{
+    /// Parses an ID3v2 header from the start of `bytes`, returning None if it doesn't begin with one.
+    pub fn parse(bytes: &[u8]) -> Option<Id3v2Header>
+    {
+        let header = bytes.get(..ID3V2_HEADER_LEN)?;
+        if &header[..3] != ID3V2_MAGIC || header[3] == 0xFF || header[4] == 0xFF
+        {
+            return None;
+        }
+        // The size is a 28 bit "sync-safe" integer, where the highest bit of each byte is always zero.
+        let mut size: u32 = 0;
+        for &byte in &header[6..10]
+        {
+            if byte & 0x80 != 0
+            {
+                return None;
+            }
+            size = (size << 7) | u32::from(byte);
+        }
+        return Some(Id3v2Header { major_version: header[3], revision: header[4], flags: header[5], size });
+    }
+
+    /// Returns the major version of the tag, e.g. 3 for ID3v2.3.
+    pub fn major_version(&self) -> u8
+    {
+        return self.major_version;
+    }
+
+    /// Returns the revision of the tag.
+    pub fn revision(&self) -> u8
+    {
+        return self.revision;
+    }
+
+    /// Returns the flags byte of the tag header.
+    pub fn flags(&self) -> u8
+    {
+        return self.flags;
+    }
+
+    /// Returns the size of the tag as written in the header, which excludes the header itself and
+    /// the footer but includes the extended header.
+    pub fn size(&self) -> u32
+    {
+        return self.size;
+    }
+
+    /// Returns true if the tag is unsynchronised, meaning a zero byte was inserted after every 0xFF
+    /// that could be mistaken for an MPEG sync word. The size counts the bytes as stored, including
+    /// the inserted zeros, so skipping the tag needs no adjustment; only reading its contents does.
+    pub fn has_unsynchronisation(&self) -> bool
+    {
+        return self.flags & UNSYNCHRONISATION != 0;
+    }
+
+    /// Returns true if an extended header follows the tag header. It's counted by `size()`, so it
+    /// doesn't change the length of the tag.
+    pub fn has_extended_header(&self) -> bool
+    {
+        return self.flags & EXTENDED_HEADER != 0;
+    }
+
+    /// Returns true if the tag ends with a ten byte footer. Only ID3v2.4 tags have footers; the same
+    /// flag is undefined in earlier versions.
+    pub fn has_footer(&self) -> bool
+    {
+        return self.major_version == 4 && self.flags & FOOTER != 0;
+    }
+
+    /// Returns the number of bytes the whole tag occupies, including the header and any footer.
+    pub fn tag_len(&self) -> usize
+    {
+        let footer = if self.has_footer() { ID3V2_HEADER_LEN } else { 0 };
+        return ID3V2_HEADER_LEN + self.size as usize + footer;
+    }
+}
+
+// Splits `data` at the first terminator for the given text encoding, which is two zero bytes for the
+// UTF-16 encodings and a single zero byte otherwise. Returns the text before it and the bytes after it.
+fn split_terminated(data: &[u8], encoding: u8) -> (&[u8], &[u8])
+{
+    if encoding == 1 || encoding == 2
+    {
+        let end = data.chunks(2).position(|pair| pair == [0, 0]).map(|index| index * 2);
+        return match end
+        {
+            Some(end) => (&data[..end], &data[end + 2..]),
+            None => (data, &[]),
+        };
+    }
+    return match data.iter().position(|&byte| byte == 0)
+    {
+        Some(end) => (&data[..end], &data[end + 1..]),
+        None => (data, &[]),
+    };
+}
+
+// Decodes text in one of the four ID3v2 text encodings: ISO-8859-1, UTF-16 with a byte order mark,
+// UTF-16BE, and UTF-8.
+fn decode_text(data: &[u8], encoding: u8) -> Option<String>
+{
+    return match encoding
+    {
+        0 => Some(data.iter().map(|&byte| char::from(byte)).collect()),
+        1 | 2 => {
+            let mut big_endian = encoding == 2;
+            let mut data = data;
+            if encoding == 1 && data.len() >= 2
+            {
+                big_endian = data[..2] == [0xFE, 0xFF];
+                data = &data[2..];
+            }
+            let units: Vec<u16> = data.chunks_exact(2)
+                .map(|pair| if big_endian { u16::from_be_bytes([pair[0], pair[1]]) } else { u16::from_le_bytes([pair[0], pair[1]]) })
+                .collect();
+            String::from_utf16(&units).ok()
+        },
+        3 => String::from_utf8(data.to_vec()).ok(),
+        _ => None,
+    };
+}
+
+// Returns the value of a TXXX or COMM frame body if its description is `iTunSMPB`.
+fn itunsmpb_value(id: &[u8], body: &[u8]) -> Option<String>
+{
+    let (&encoding, rest) = body.split_first()?;
+    let rest = match id
+    {
+        b"TXXX" | b"TXX" => rest,
+        // Comments have a three letter language code before the description.
+        b"COMM" | b"COM" => rest.get(3..)?,
+        _ => return None,
+    };
+    let (description, value) = split_terminated(rest, encoding);
+    if decode_text(description, encoding)? != ITUNSMPB
+    {
+        return None;
+    }
+    let (value, _) = split_terminated(value, encoding);
+    return decode_text(value, encoding);
+}
+
+// Parses the encoder delay and padding from an iTunSMPB value, which is a list of hexadecimal fields
+// separated by spaces. The second and third fields hold the delay and padding.
+fn parse_itunsmpb(value: &str) -> Option<GaplessInfo>
+{
+    let mut fields = value.split_whitespace().skip(1);
+    let delay = u32::from_str_radix(fields.next()?, 16).ok()?;
+    let padding = u32::from_str_radix(fields.next()?, 16).ok()?;
+    return Some(GaplessInfo { delay, padding });
+}
+
+// Reverses unsynchronisation by removing the zero byte that follows every 0xFF.
+fn resynchronise(data: &[u8]) -> Vec<u8>
+{
+    let mut bytes = Vec::with_capacity(data.len());
+    let mut after_ff = false;
+    for &byte in data
+    {
+        if !(after_ff && byte == 0)
+        {
+            bytes.push(byte);
+        }
+        after_ff = byte == 0xFF;
+    }
+    return bytes;
+}
+
+// Searches the frames of a whole ID3v2 tag for the iTunSMPB comment or user text frame that iTunes
+// uses to store the encoder delay and padding. Before ID3v2.4, unsynchronisation applies to the whole
+// tag and frame sizes count the original bytes, so the tag is resynchronised before its frames are
+// read. In ID3v2.4 frame sizes count the stored bytes, so each frame body is resynchronised instead.
+pub(crate) fn itunes_gapless(tag: &[u8]) -> Option<GaplessInfo>
+{
+    let header = Id3v2Header::parse(tag)?;
+    let resynchronised;
+    let tag = if header.has_unsynchronisation() && header.major_version < 4
+    {
+        let end = tag.len().min(ID3V2_HEADER_LEN + header.size as usize);
+        let mut bytes = tag[..ID3V2_HEADER_LEN].to_vec();
+        bytes.extend(resynchronise(&tag[ID3V2_HEADER_LEN..end]));
+        resynchronised = bytes;
+        &resynchronised[..]
+    }
+    else
+    {
+        tag
+    };
+    let end = tag.len().min(ID3V2_HEADER_LEN + header.size as usize);
+    let mut pos = ID3V2_HEADER_LEN;
+    if header.has_extended_header()
+    {
+        let size = tag.get(pos..pos + 4)?;
+        pos += match header.major_version
+        {
+            // The ID3v2.4 size is sync-safe and includes itself, while the ID3v2.3 size doesn't.
+            4 => size.iter().fold(0, |size, &byte| (size << 7) | usize::from(byte & 0x7F)),
+            _ => 4 + size.iter().fold(0, |size, &byte| (size << 8) | usize::from(byte)),
+        };
+    }
+    // ID3v2.2 frames have a three byte ID and size, later versions have four bytes of each and two flag bytes.
+    let (id_len, header_len) = if header.major_version == 2 { (3, 6) } else { (4, 10) };
+    while pos + header_len <= end
+    {
+        let id = &tag[pos..pos + id_len];
+        if id[0] == 0
+        {
+            // The rest of the tag is padding.
+            break;
+        }
+        let size_bytes = &tag[pos + id_len..pos + 2 * id_len];
+        let size = match header.major_version
+        {
+            4 => size_bytes.iter().fold(0, |size, &byte| (size << 7) | usize::from(byte & 0x7F)),
+            _ => size_bytes.iter().fold(0, |size, &byte| (size << 8) | usize::from(byte)),
+        };
+        let mut body = tag.get(pos + header_len..(pos + header_len).checked_add(size)?.min(end))?.to_vec();
+        if header.has_unsynchronisation() && header.major_version == 4
+        {
+            body = resynchronise(&body);
+        }
+        if let Some(gapless) = itunsmpb_value(id, &body).as_deref().and_then(parse_itunsmpb)
+        {
+            return Some(gapless);
+        }
+        pos += header_len + size;
+    }
+    return None;
+}
+
+/// Returns the number of bytes to skip to get past the ID3v2 tag at the start of `bytes`, or None if
+/// `bytes` doesn't begin with an ID3v2 tag header. Only the ten byte header needs to be supplied. The
+/// size in the header already counts the zeros inserted by unsynchronisation, so it needs no adjustment.
+pub fn skip_id3v2(bytes: &[u8]) -> Option<usize>
+{
+    return Id3v2Header::parse(bytes).map(|header| header.tag_len());
+}
+
+#[cfg(test)]
+pub(crate) mod tests
+{
+    use super::*;
+
+    // Returns the bytes of an ID3v2.3 tag with the given body.
+    pub(crate) fn id3v2_tag_bytes(body: &[u8]) -> Vec<u8>
+    {
+        let size = body.len() as u32;
+        let mut bytes = b"ID3".to_vec();
+        bytes.extend_from_slice(&[3, 0, 0]);
+        bytes.extend_from_slice(&[(size >> 21) as u8 & 0x7F, (size >> 14) as u8 & 0x7F, (size >> 7) as u8 & 0x7F, size as u8 & 0x7F]);
+        bytes.extend_from_slice(body);
+        return bytes;
+    }
+
+    // Returns the bytes of an ID3v2.3 frame with the given ID and body.
+    pub(crate) fn id3v2_frame_bytes(id: &[u8; 4], body: &[u8]) -> Vec<u8>
+    {
+        let mut bytes = id.to_vec();
+        bytes.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&[0, 0]);
+        bytes.extend_from_slice(body);
+        return bytes;
+    }
+
+    /// Verifies that itunes_gapless() reads the delay and padding from a TXXX frame.
+    #[test]
+    fn test_itunes_gapless_txxx()
+    {
+        let mut body = vec![0];
+        body.extend_from_slice(b"iTunSMPB\0 00000000 00000840 000001C4 00000000000FC3FC 00000000");
+        let mut frames = id3v2_frame_bytes(b"TIT2", b"\0Song");
+        frames.extend(id3v2_frame_bytes(b"TXXX", &body));
+        frames.extend_from_slice(&[0; 16]);
+        let gapless = itunes_gapless(&id3v2_tag_bytes(&frames)).unwrap();
+        assert_eq!(gapless, GaplessInfo { delay: 0x840, padding: 0x1C4 });
+    }
+
+    /// Verifies that itunes_gapless() reads the delay and padding from a UTF-16 COMM frame.
+    #[test]
+    fn test_itunes_gapless_comm_utf16()
+    {
+        let utf16 = |text: &str| {
+            let mut bytes = vec![0xFF, 0xFE];
+            bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            bytes
+        };
+        let mut body = vec![1];
+        body.extend_from_slice(b"eng");
+        body.extend(utf16("iTunSMPB"));
+        body.extend_from_slice(&[0, 0]);
+        body.extend(utf16(" 00000000 00000210 00000A2C 000000000003B1A4"));
+        let frames = id3v2_frame_bytes(b"COMM", &body);
+        let gapless = itunes_gapless(&id3v2_tag_bytes(&frames)).unwrap();
+        assert_eq!(gapless, GaplessInfo { delay: 0x210, padding: 0xA2C });
+
+        let frames = id3v2_frame_bytes(b"COMM", b"\0engComment\0 00000000 00000210 00000A2C");
+        assert_eq!(itunes_gapless(&id3v2_tag_bytes(&frames)), None);
+    }
+
+    /// Verifies that itunes_gapless() reverses unsynchronisation, which would otherwise throw off the
+    /// position of every frame after one holding a 0xFF byte.
+    #[test]
+    fn test_itunes_gapless_unsynchronised()
+    {
+        let mut body = vec![0];
+        body.extend_from_slice(b"iTunSMPB\0 00000000 00000840 000001C4");
+        // The frame size counts the title as it was before a zero was inserted after its 0xFF byte.
+        let mut frames = id3v2_frame_bytes(b"TIT2", &[0, b'A', 0xFF, 0xE0]);
+        frames.insert(13, 0);
+        frames.extend(id3v2_frame_bytes(b"TXXX", &body));
+        let mut tag = id3v2_tag_bytes(&frames);
+        tag[5] = UNSYNCHRONISATION;
+        assert!(Id3v2Header::parse(&tag).unwrap().has_unsynchronisation());
+        assert_eq!(itunes_gapless(&tag), Some(GaplessInfo { delay: 0x840, padding: 0x1C4 }));
+        assert_eq!(skip_id3v2(&tag), Some(tag.len()));
+        assert_eq!(resynchronise(&[0xFF, 0x00, 0x00, 0xFF, 0x00, 0xFF, 0xE0]), vec![0xFF, 0x00, 0xFF, 0xFF, 0xE0]);
+    }
+
+    /// Verifies that Id3v2Header::parse() decodes the sync-safe size.
+    #[test]
+    fn test_id3v2_header_parse()
+    {
+        let header = Id3v2Header::parse(&[b'I', b'D', b'3', 4, 0, 0x10, 0x00, 0x00, 0x02, 0x01]).unwrap();
+        assert_eq!(header.major_version(), 4);
+        assert_eq!(header.revision(), 0);
+        assert_eq!(header.flags(), 0x10);
+        assert_eq!(header.size(), 257);
+        // The flags mark a footer, which follows the 257 bytes counted by the size.
+        assert!(header.has_footer());
+        assert_eq!(header.tag_len(), 277);
+    }
+
+    /// Verifies that skip_id3v2() counts the footer of an ID3v2.4 tag but not the extended header,
+    /// which is already part of the size, and ignores the footer flag in earlier versions.
+    #[test]
+    fn test_skip_id3v2_extended_header_and_footer()
+    {
+        // An ID3v2.4 tag with both flags set, a 6 byte extended header, and 20 bytes of frames.
+        let mut tag = vec![b'I', b'D', b'3', 4, 0, EXTENDED_HEADER | FOOTER, 0, 0, 0, 26];
+        tag.extend_from_slice(&[0, 0, 0, 6, 1, 0]);
+        tag.extend_from_slice(&[0; 20]);
+        tag.extend_from_slice(&[b'3', b'D', b'I', 4, 0, EXTENDED_HEADER | FOOTER, 0, 0, 0, 26]);
+        let header = Id3v2Header::parse(&tag).unwrap();
+        assert!(header.has_extended_header());
+        assert_eq!(skip_id3v2(&tag), Some(tag.len()));
+
+        // ID3v2.3 has no footer, so the same flag doesn't add to the length.
+        tag[3] = 3;
+        assert!(!Id3v2Header::parse(&tag).unwrap().has_footer());
+        assert_eq!(skip_id3v2(&tag), Some(36));
+    }
+
+    /// Verifies that skip_id3v2() rejects headers that aren't valid.
+    #[test]
+    fn test_skip_id3v2_invalid()
+    {
+        assert_eq!(skip_id3v2(b"ID3"), None);
+        assert_eq!(skip_id3v2(&[b'T', b'A', b'G', 3, 0, 0, 0, 0, 0, 0]), None);
+        assert_eq!(skip_id3v2(&[b'I', b'D', b'3', 0xFF, 0, 0, 0, 0, 0, 0]), None);
+        assert_eq!(skip_id3v2(&[b'I', b'D', b'3', 3, 0, 0, 0, 0, 0x80, 0]), None);
+        assert_eq!(skip_id3v2(&id3v2_tag_bytes(&[0; 300])), Some(310));
+    }
+}
@@ -0,0 +1,180 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use super::{FrameHeader, ParseOptions};
+
+// The version written by `Mp3Summary::to_bytes()`. Later versions may only append fields, so a blob
+// of any version can be read by skipping the fields that come after the ones known here.
+const VERSION: u8 = 1;
+
+// These constants are the bits of the flags byte.
+const HAS_FORMAT: u8 =  0x01; // The 4 bytes of the format hold a frame header
+const IS_VBR: u8 =      0x02; // The stream is variable bitrate
+const HAS_ENCODER: u8 = 0x04; // An encoder name follows the fixed fields
+
+// The number of bytes before the encoder name: version, flags, format, duration, frame count, and bitrate.
+const FIXED_LEN: usize = 1 + 1 + 4 + 8 + 8 + 4;
+
+// The format is read back leniently, since `Mp3::with_options()` may have accepted it that way.
+const LENIENT: ParseOptions = ParseOptions {
+    lenient_emphasis: true,
+    lenient_layer2_combos: true,
+    require_contiguous: false,
+    max_sync_search: None,
+    stop_on_error: false,
+};
+
+/// A compact summary of a stream, as returned by `Mp3::summary()`, which can be cached as bytes and
+/// reloaded without parsing the audio again
+#[derive(Clone, PartialEq, Debug)]
+pub struct Mp3Summary
+{
+    /// Header of the first valid frame, which describes the format of the stream.
+    pub format: Option<FrameHeader>,
+    /// Playback duration, leaving out the encoder delay and padding when they're known.
+    pub duration: Duration,
+    /// Number of frames that hold audio, leaving out a leading Xing, Info, or VBRI frame.
+    pub frame_count: u64,
+    /// Bitrate averaged over the duration, in bits per second.
+    pub average_bitrate: u32,
+    /// True if the stream is variable bitrate, as reported by `Mp3::is_vbr()`.
+    pub is_vbr: bool,
+    /// Name of the encoder, as reported by `Mp3::encoder()`.
+    pub encoder: Option<String>,
+}
+
+impl Mp3Summary
+{
+    /// Serializes the summary into a versioned layout of big-endian fields. The encoder name is
+    /// truncated to 255 bytes.
+    pub fn to_bytes(&self) -> Vec<u8>
+    {
+        let mut flags = 0;
+        if self.format.is_some()
+        {
+            flags |= HAS_FORMAT;
+        }
+        if self.is_vbr
+        {
+            flags |= IS_VBR;
+        }
+        if self.encoder.is_some()
+        {
+            flags |= HAS_ENCODER;
+        }
+        let mut bytes = Vec::with_capacity(FIXED_LEN + 1);
+        bytes.push(VERSION);
+        bytes.push(flags);
+        bytes.extend_from_slice(&self.format.map_or([0; 4], |header| header.to_bytes()));
+        bytes.extend_from_slice(&u64::try_from(self.duration.as_nanos()).unwrap_or(u64::MAX).to_be_bytes());
+        bytes.extend_from_slice(&self.frame_count.to_be_bytes());
+        bytes.extend_from_slice(&self.average_bitrate.to_be_bytes());
+        if let Some(ref encoder) = self.encoder
+        {
+            let name = &encoder.as_bytes()[..encoder.len().min(255)];
+            bytes.push(name.len() as u8);
+            bytes.extend_from_slice(name);
+        }
+        return bytes;
+    }
+
+    /// Reads a summary written by `Mp3Summary::to_bytes()`. Fields added by later versions of the
+    /// layout are ignored, so caches written by newer versions of the crate can still be read.
+    /// Returns None if the bytes are cut short or weren't written by `to_bytes()`.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Mp3Summary>
+    {
+        if bytes.len() < FIXED_LEN || bytes[0] == 0
+        {
+            return None;
+        }
+        let flags = bytes[1];
+        let format = match flags & HAS_FORMAT
+        {
+            0 => None,
+            _ => Some(FrameHeader::new_with_options([bytes[2], bytes[3], bytes[4], bytes[5]], LENIENT).ok()?),
+        };
+        let read_u64 = |pos: usize| {
+            let mut field = [0; 8];
+            field.copy_from_slice(&bytes[pos..pos + 8]);
+            return u64::from_be_bytes(field);
+        };
+        let encoder = match flags & HAS_ENCODER
+        {
+            0 => None,
+            _ => {
+                let len = usize::from(*bytes.get(FIXED_LEN)?);
+                let name = bytes.get(FIXED_LEN + 1..FIXED_LEN + 1 + len)?;
+                Some(String::from_utf8_lossy(name).into_owned())
+            },
+        };
+        return Some(Mp3Summary {
+            format,
+            duration: Duration::from_nanos(read_u64(6)),
+            frame_count: read_u64(14),
+            average_bitrate: u32::from_be_bytes([bytes[22], bytes[23], bytes[24], bytes[25]]),
+            is_vbr: flags & IS_VBR != 0,
+            encoder,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // MPEG Version 1, Layer III, 128Kbps, 44.1KHz, Joint Stereo
+    const STEREO_HEADER: [u8; 4] = [0b1111_1111, 0b1111_1011, 0b1001_0000, 0b0100_0100];
+
+    /// Verifies that Mp3Summary::from_bytes() reads what Mp3Summary::to_bytes() writes, skips fields
+    /// appended by later versions, and rejects blobs that are cut short.
+    #[test]
+    fn test_summary_round_trip()
+    {
+        let summary = Mp3Summary {
+            format: Some(FrameHeader::new(STEREO_HEADER).unwrap()),
+            duration: Duration::from_nanos(183_275_102_040),
+            frame_count: 7_016,
+            average_bitrate: 192_415,
+            is_vbr: true,
+            encoder: Some("LAME3.100".to_string()),
+        };
+        let mut bytes = summary.to_bytes();
+        assert_eq!(bytes.len(), FIXED_LEN + 10);
+
+        // A later version with a field appended after the encoder name
+        bytes[0] = VERSION + 1;
+        bytes.extend_from_slice(&[0xAB; 6]);
+        assert_eq!(Mp3Summary::from_bytes(&bytes), Some(summary.clone()));
+
+        let empty = Mp3Summary { format: None, duration: Duration::from_secs(0), frame_count: 0, average_bitrate: 0, is_vbr: false, encoder: None };
+        assert_eq!(Mp3Summary::from_bytes(&empty.to_bytes()), Some(empty));
+
+        assert!(Mp3Summary::from_bytes(&summary.to_bytes()[..FIXED_LEN + 5]).is_none());
+        assert!(Mp3Summary::from_bytes(&[0; FIXED_LEN]).is_none());
+    }
+
+    /// Verifies that a format only accepted by lenient ParseOptions, with reserved emphasis or a
+    /// prohibited Layer II combination, survives the round trip.
+    #[test]
+    fn test_summary_round_trip_lenient_format()
+    {
+        // MPEG Version 1, Layer III, 128Kbps, 44.1KHz, Joint Stereo, reserved emphasis
+        let reserved = [0xFF, 0xFB, 0x90, 0x46];
+        // MPEG Version 1, Layer II, 32Kbps, 44.1KHz, Stereo, which is only allowed in single channel mode
+        let prohibited = [0xFF, 0xFD, 0x10, 0x04];
+        for head in [reserved, prohibited]
+        {
+            assert!(FrameHeader::new(head).is_err());
+            let summary = Mp3Summary {
+                format: Some(FrameHeader::new_with_options(head, LENIENT).unwrap()),
+                duration: Duration::from_secs(1),
+                frame_count: 39,
+                average_bitrate: 128_000,
+                is_vbr: false,
+                encoder: None,
+            };
+            assert_eq!(Mp3Summary::from_bytes(&summary.to_bytes()), Some(summary));
+        }
+    }
+}
@@ -0,0 +1,686 @@
+use super::{ChannelMode, FrameHeader, MpegVersion};
+
+// These constants are the bits of the Xing header's flags field that mark which of the optional
+// fields are present. See the link below for further details.
+// https://www.codeproject.com/Articles/8295/MPEG-Audio-Frame-Header#XINGHeader
+const FRAMES_FLAG: u32 =    0x00_00_00_01;
+const BYTES_FLAG: u32 =     0x00_00_00_02;
+const TOC_FLAG: u32 =       0x00_00_00_04;
+const QUALITY_FLAG: u32 =   0x00_00_00_08;
+
+// The VBRI header always begins 32 bytes after the frame header, regardless of the channel mode.
+const VBRI_OFFSET: usize = 32;
+
+/// Which of the two tags a Xing header was written with
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum XingKind
+{
+    Xing,   // Variable bitrate stream
+    Info,   // Constant bitrate stream
+}
+
+/// The Xing header written into the first frame of a stream by most Layer III encoders. CBR files
+/// use the tag `Info` in place of `Xing` but share the same layout.
+#[derive(Clone, PartialEq, Debug)]
+pub struct XingHeader
+{
+    kind: XingKind,             // Whether the tag was `Xing` or `Info`
+    flags: u32,                 // Which of the optional fields below are present
+    frames: Option<u32>,        // Number of audio frames in the stream, excluding the Xing frame
+    bytes: Option<u32>,         // Number of bytes in the stream, including the Xing frame
+    toc: Option<[u8; 100]>,     // Table of contents used for seeking
+    quality: Option<u32>,       // Encoder quality indicator from 0 (best) to 100 (worst)
+}
+
+impl XingHeader
+{
+    /// The number of bytes a Xing header with every optional field occupies.
+    pub const MAX_LEN: usize = 120;
+
+    /// Creates a Xing header with the frame count, byte count, and table of contents fields set.
+    pub fn new(frames: u32, bytes: u32, toc: [u8; 100]) -> XingHeader
+    {
+        XingHeader {
+            kind: XingKind::Xing,
+            flags: FRAMES_FLAG | BYTES_FLAG | TOC_FLAG,
+            frames: Some(frames),
+            bytes: Some(bytes),
+            toc: Some(toc),
+            quality: None,
+        }
+    }
+
+    /// Replaces the frame count, byte count, and table of contents with new values. Fields the flags
+    /// don't mark as present are left out, so the serialized length of the header doesn't change.
+    pub fn update(&mut self, frames: u32, bytes: u32, toc: [u8; 100])
+    {
+        if self.has_frame_count()
+        {
+            self.frames = Some(frames);
+        }
+        if self.has_byte_count()
+        {
+            self.bytes = Some(bytes);
+        }
+        if self.has_toc()
+        {
+            self.toc = Some(toc);
+        }
+    }
+
+    /// Returns whether the header was tagged `Xing` for a VBR stream or `Info` for a CBR stream.
+    pub fn kind(&self) -> XingKind
+    {
+        return self.kind;
+    }
+
+    /// Returns true if the flags mark the frame count field as present.
+    pub fn has_frame_count(&self) -> bool
+    {
+        return self.flags & FRAMES_FLAG != 0;
+    }
+
+    /// Returns true if the flags mark the byte count field as present.
+    pub fn has_byte_count(&self) -> bool
+    {
+        return self.flags & BYTES_FLAG != 0;
+    }
+
+    /// Returns true if the flags mark the table of contents as present.
+    pub fn has_toc(&self) -> bool
+    {
+        return self.flags & TOC_FLAG != 0;
+    }
+
+    /// Returns true if the flags mark the quality indicator as present.
+    pub fn has_quality(&self) -> bool
+    {
+        return self.flags & QUALITY_FLAG != 0;
+    }
+
+    /// Returns the number of audio frames in the stream, excluding the Xing frame itself.
+    pub fn frames(&self) -> Option<u32>
+    {
+        return self.frames;
+    }
+
+    /// Returns the number of bytes in the stream, including the Xing frame itself.
+    pub fn bytes(&self) -> Option<u32>
+    {
+        return self.bytes;
+    }
+
+    /// Returns the table of contents. Each entry is the position of the frame at that percentage
+    /// of the duration, scaled so the full stream is 256.
+    pub fn toc(&self) -> Option<&[u8; 100]>
+    {
+        return self.toc.as_ref();
+    }
+
+    /// Returns the encoder quality indicator.
+    pub fn quality(&self) -> Option<u32>
+    {
+        return self.quality;
+    }
+
+    /// Converts the table of contents into byte offsets, where entry `i` is the approximate position
+    /// of the frame at `i` percent of the duration. `total_bytes` is the length of the stream starting
+    /// from the Xing frame, usually `XingHeader::bytes()`, and the offsets are measured from the start
+    /// of the Xing frame, so the offset of the frame in the file must be added. The offsets are only
+    /// accurate to 1/256 of the stream and won't land exactly on a frame header.
+    pub fn toc_offsets(&self, total_bytes: u32) -> Option<[u64; 100]>
+    {
+        let toc = self.toc.as_ref()?;
+        let mut offsets = [0u64; 100];
+        for (offset, &entry) in offsets.iter_mut().zip(toc.iter())
+        {
+            *offset = u64::from(entry) * u64::from(total_bytes) / 256;
+        }
+        return Some(offsets);
+    }
+
+    /// Serializes the header into the bytes that follow the side information of the Xing frame.
+    pub fn to_bytes(&self) -> Vec<u8>
+    {
+        let mut bytes = Vec::with_capacity(XingHeader::MAX_LEN);
+        bytes.extend_from_slice(match self.kind
+        {
+            XingKind::Xing => b"Xing",
+            XingKind::Info => b"Info",
+        });
+        bytes.extend_from_slice(&self.flags.to_be_bytes());
+        if let Some(frames) = self.frames
+        {
+            bytes.extend_from_slice(&frames.to_be_bytes());
+        }
+        if let Some(byte_count) = self.bytes
+        {
+            bytes.extend_from_slice(&byte_count.to_be_bytes());
+        }
+        if let Some(toc) = self.toc
+        {
+            bytes.extend_from_slice(&toc);
+        }
+        if let Some(quality) = self.quality
+        {
+            bytes.extend_from_slice(&quality.to_be_bytes());
+        }
+        return bytes;
+    }
+}
+
+/// The VBRI header written into the first frame of a stream by the Fraunhofer encoder.
+#[derive(Clone, PartialEq, Debug)]
+pub struct VbriHeader
+{
+    version: u16,   // Version of the VBRI header
+    delay: u16,     // Encoder delay
+    quality: u16,   // Encoder quality indicator
+    bytes: u32,     // Number of bytes in the stream
+    frames: u32,    // Number of frames in the stream
+    seek_table: Vec<u32>,   // Offset of each seek point from the start of the VBRI frame, ending with the end of the stream
+}
+
+impl VbriHeader
+{
+    /// Returns the version of the VBRI header.
+    pub fn version(&self) -> u16
+    {
+        return self.version;
+    }
+
+    /// Returns the encoder delay.
+    pub fn delay(&self) -> u16
+    {
+        return self.delay;
+    }
+
+    /// Returns the encoder quality indicator.
+    pub fn quality(&self) -> u16
+    {
+        return self.quality;
+    }
+
+    /// Returns the number of bytes in the stream.
+    pub fn bytes(&self) -> u32
+    {
+        return self.bytes;
+    }
+
+    /// Returns the number of frames in the stream.
+    pub fn frames(&self) -> u32
+    {
+        return self.frames;
+    }
+
+    /// Returns the byte offsets of the seek points, measured from the start of the VBRI frame. The
+    /// seek points divide the stream into parts of equal duration, so the first offset is 0 and the
+    /// last is the end of the stream. Empty if the header has no seek table.
+    pub fn seek_table(&self) -> &[u32]
+    {
+        return &self.seek_table;
+    }
+
+    /// Returns the approximate byte offset of the point `time_fraction` of the way through the
+    /// duration, where 0 is the start and 1 the end. The offset is measured from the start of the
+    /// VBRI frame and interpolated between seek points, so it won't land exactly on a frame header.
+    /// Returns None if there's no seek table or `time_fraction` is outside 0 to 1.
+    pub fn seek_offset(&self, time_fraction: f32) -> Option<u64>
+    {
+        if self.seek_table.len() < 2 || !(0.0..=1.0).contains(&time_fraction)
+        {
+            return None;
+        }
+        let parts = self.seek_table.len() - 1;
+        let position = f64::from(time_fraction) * parts as f64;
+        let index = (position as usize).min(parts - 1);
+        let start = f64::from(self.seek_table[index]);
+        let end = f64::from(self.seek_table[index + 1]);
+        return Some((start + (end - start) * (position - index as f64)) as u64);
+    }
+}
+
+/// The LAME extension that follows the Xing header in files encoded by LAME and compatible encoders
+#[derive(Clone, PartialEq, Debug)]
+pub struct LameTag
+{
+    encoder: String,        // Name and version of the encoder, e.g. "LAME3.100"
+    vbr_method: u8,         // How the bitrate was chosen, from the low nibble of the byte after the encoder
+    encoder_delay: u16,     // Number of samples of silence the encoder added to the start
+    encoder_padding: u16,   // Number of samples of silence the encoder added to the end
+}
+
+impl LameTag
+{
+    /// Returns the name and version of the encoder, e.g. `LAME3.100`, without any NUL or space padding.
+    pub fn encoder(&self) -> &str
+    {
+        return &self.encoder;
+    }
+
+    /// Returns the VBR method code: 1 for CBR, 2 for ABR, 3 to 6 for the VBR algorithms, 8 for two
+    /// pass CBR, 9 for two pass ABR, and 0 when unknown.
+    pub fn vbr_method(&self) -> u8
+    {
+        return self.vbr_method;
+    }
+
+    /// Returns the number of samples of silence the encoder added to the start of the stream.
+    pub fn encoder_delay(&self) -> u16
+    {
+        return self.encoder_delay;
+    }
+
+    /// Returns the number of samples of silence the encoder added to the end of the stream.
+    pub fn encoder_padding(&self) -> u16
+    {
+        return self.encoder_padding;
+    }
+}
+
+// Returns the length of the Layer III side information, which is where the Xing header begins.
+pub(crate) fn side_info_len(header: &FrameHeader) -> usize
+{
+    let mono = header.channel_mode == ChannelMode::SingleChannel;
+    let side_info = match (header.mpeg_version, mono)
+    {
+        (MpegVersion::Version1, false) => 32,
+        (MpegVersion::Version1, true) => 17,
+        (_, false) => 17,
+        (_, true) => 9,
+    };
+    // The 16 bit CRC sits between the frame header and the side information.
+    return side_info + header.crc_len();
+}
+
+// Reads a big-endian u32 at `pos`, or None if the slice is too short.
+fn read_u32(data: &[u8], pos: usize) -> Option<u32>
+{
+    let bytes = data.get(pos..pos + 4)?;
+    return Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+}
+
+// Reads a big-endian u16 at `pos`, or None if the slice is too short.
+fn read_u16(data: &[u8], pos: usize) -> Option<u16>
+{
+    let bytes = data.get(pos..pos + 2)?;
+    return Some(u16::from_be_bytes([bytes[0], bytes[1]]));
+}
+
+/// Parses the Xing or Info header from the data portion of a frame, returning None if the frame
+/// doesn't contain one.
+pub fn parse_xing(header: &FrameHeader, data: &[u8]) -> Option<XingHeader>
+{
+    let mut pos = side_info_len(header);
+    let kind = match data.get(pos..pos + 4)?
+    {
+        b"Xing" => XingKind::Xing,
+        b"Info" => XingKind::Info,
+        _ => return None,
+    };
+    let flags = read_u32(data, pos + 4)?;
+    pos += 8;
+
+    let mut frames = None;
+    if flags & FRAMES_FLAG != 0
+    {
+        frames = Some(read_u32(data, pos)?);
+        pos += 4;
+    }
+    let mut bytes = None;
+    if flags & BYTES_FLAG != 0
+    {
+        bytes = Some(read_u32(data, pos)?);
+        pos += 4;
+    }
+    let mut toc = None;
+    if flags & TOC_FLAG != 0
+    {
+        let mut entries = [0u8; 100];
+        entries.copy_from_slice(data.get(pos..pos + 100)?);
+        toc = Some(entries);
+        pos += 100;
+    }
+    let mut quality = None;
+    if flags & QUALITY_FLAG != 0
+    {
+        quality = Some(read_u32(data, pos)?);
+    }
+    return Some(XingHeader { kind, flags, frames, bytes, toc, quality });
+}
+
+/// Parses the LAME extension from the data portion of a frame containing a Xing or Info header,
+/// returning None if the frame doesn't contain one.
+pub fn parse_lame(header: &FrameHeader, data: &[u8]) -> Option<LameTag>
+{
+    parse_xing(header, data)?;
+    // LAME always writes every field of the Xing header, so its extension begins at a fixed offset.
+    let pos = side_info_len(header) + XingHeader::MAX_LEN;
+    let encoder = data.get(pos..pos + 9)?;
+    if !encoder[..4].iter().all(u8::is_ascii_alphanumeric)
+    {
+        return None;
+    }
+    // The byte after the encoder string holds the tag revision and the VBR method.
+    let vbr_method = data.get(pos + 9)? & 0x0F;
+    // The delay and padding are packed into 3 bytes as two 12 bit values.
+    let packed = data.get(pos + 21..pos + 24)?;
+    let encoder_delay = (u16::from(packed[0]) << 4) | (u16::from(packed[1]) >> 4);
+    let encoder_padding = (u16::from(packed[1] & 0x0F) << 8) | u16::from(packed[2]);
+    // The string is padded to 9 bytes with NULs or spaces. It's ASCII, so each byte maps to a char.
+    let encoder: String = encoder.iter().map(|&byte| char::from(byte)).collect();
+    let encoder = encoder.trim_end_matches(['\0', ' ']).to_string();
+    return Some(LameTag { encoder, vbr_method, encoder_delay, encoder_padding });
+}
+
+/// Parses the VBRI header from the data portion of a frame, returning None if the frame doesn't
+/// contain one.
+pub fn parse_vbri(_header: &FrameHeader, data: &[u8]) -> Option<VbriHeader>
+{
+    if data.get(VBRI_OFFSET..VBRI_OFFSET + 4)? != b"VBRI"
+    {
+        return None;
+    }
+    return Some(VbriHeader {
+        version: read_u16(data, VBRI_OFFSET + 4)?,
+        delay: read_u16(data, VBRI_OFFSET + 6)?,
+        quality: read_u16(data, VBRI_OFFSET + 8)?,
+        bytes: read_u32(data, VBRI_OFFSET + 10)?,
+        frames: read_u32(data, VBRI_OFFSET + 14)?,
+        seek_table: parse_vbri_seek_table(data).unwrap_or_default(),
+    });
+}
+
+// Reads the VBRI seek table, whose entries are the sizes of consecutive parts of the stream, and
+// converts it into the offset of each part. Returns None if the table is cut short or its entries
+// aren't 1 to 4 bytes long.
+fn parse_vbri_seek_table(data: &[u8]) -> Option<Vec<u32>>
+{
+    let entries = usize::from(read_u16(data, VBRI_OFFSET + 18)?);
+    let scale = u32::from(read_u16(data, VBRI_OFFSET + 20)?);
+    let entry_len = usize::from(read_u16(data, VBRI_OFFSET + 22)?);
+    if entries == 0 || !(1..=4).contains(&entry_len)
+    {
+        return None;
+    }
+    let start = VBRI_OFFSET + 26;
+    let table = data.get(start..start + entries * entry_len)?;
+    let mut offsets = Vec::with_capacity(entries + 1);
+    let mut offset = 0u32;
+    offsets.push(offset);
+    for entry in table.chunks_exact(entry_len)
+    {
+        let size = entry.iter().fold(0u32, |size, &byte| (size << 8) | u32::from(byte));
+        offset = offset.saturating_add(size.saturating_mul(scale));
+        offsets.push(offset);
+    }
+    return Some(offsets);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // MPEG Version 1, Layer III, 128Kbps, 44.1KHz, Joint Stereo
+    const STEREO_HEADER: [u8; 4] = [0b1111_1111, 0b1111_1011, 0b1001_0000, 0b0100_0100];
+
+    /// Verifies that parse_xing() reads every field of a Xing header with all flags set.
+    #[test]
+    fn test_parse_xing()
+    {
+        let header = FrameHeader::new(STEREO_HEADER).unwrap();
+        let mut data = vec![0u8; 413];
+        let mut toc = [0u8; 100];
+        toc[99] = 250;
+        data[32..36].copy_from_slice(b"Xing");
+        data[36..40].copy_from_slice(&0x0Fu32.to_be_bytes());
+        data[40..44].copy_from_slice(&1_000u32.to_be_bytes());
+        data[44..48].copy_from_slice(&417_000u32.to_be_bytes());
+        data[48..148].copy_from_slice(&toc);
+        data[148..152].copy_from_slice(&57u32.to_be_bytes());
+
+        let xing = parse_xing(&header, &data).unwrap();
+        assert_eq!(xing.kind(), XingKind::Xing);
+        assert_eq!(xing.frames(), Some(1_000));
+        assert_eq!(xing.bytes(), Some(417_000));
+        assert_eq!(xing.toc(), Some(&toc));
+        assert_eq!(xing.quality(), Some(57));
+    }
+
+    /// Verifies that parse_xing() reports the Info tag of a CBR stream.
+    #[test]
+    fn test_parse_xing_info()
+    {
+        let header = FrameHeader::new(STEREO_HEADER).unwrap();
+        let mut data = vec![0u8; 413];
+        data[32..36].copy_from_slice(b"Info");
+        data[36..40].copy_from_slice(&0x01u32.to_be_bytes());
+        data[40..44].copy_from_slice(&1_000u32.to_be_bytes());
+
+        let xing = parse_xing(&header, &data).unwrap();
+        assert_eq!(xing.kind(), XingKind::Info);
+        assert_eq!(xing.frames(), Some(1_000));
+        assert_eq!(xing.bytes(), None);
+        assert_eq!(&xing.to_bytes()[..4], b"Info");
+    }
+
+    /// Verifies that parse_xing() only reads the fields marked present by the flags.
+    #[test]
+    fn test_parse_xing_frames_only()
+    {
+        let header = FrameHeader::new(STEREO_HEADER).unwrap();
+        let mut data = vec![0xAAu8; 413];
+        data[32..36].copy_from_slice(b"Xing");
+        data[36..40].copy_from_slice(&0x01u32.to_be_bytes());
+        data[40..44].copy_from_slice(&1_000u32.to_be_bytes());
+
+        let xing = parse_xing(&header, &data).unwrap();
+        assert!(xing.has_frame_count());
+        assert!(!xing.has_byte_count());
+        assert!(!xing.has_toc());
+        assert!(!xing.has_quality());
+        assert_eq!(xing.frames(), Some(1_000));
+        assert_eq!(xing.bytes(), None);
+        assert_eq!(xing.toc(), None);
+        assert_eq!(xing.quality(), None);
+    }
+
+    /// Verifies that parse_xing() returns None when the tag is missing.
+    #[test]
+    fn test_parse_xing_missing()
+    {
+        let header = FrameHeader::new(STEREO_HEADER).unwrap();
+        let data = vec![0u8; 413];
+        assert_eq!(parse_xing(&header, &data), None);
+    }
+
+    /// Verifies that XingHeader::to_bytes() produces bytes that parse_xing() reads back.
+    #[test]
+    fn test_xing_to_bytes_round_trip()
+    {
+        let header = FrameHeader::new(STEREO_HEADER).unwrap();
+        let mut toc = [0u8; 100];
+        for (i, entry) in toc.iter_mut().enumerate()
+        {
+            *entry = (i * 2) as u8;
+        }
+        let xing = XingHeader::new(42, 17_514, toc);
+        let mut data = vec![0u8; 413];
+        let bytes = xing.to_bytes();
+        assert_eq!(bytes.len(), 116);
+        data[32..32 + bytes.len()].copy_from_slice(&bytes);
+        assert_eq!(parse_xing(&header, &data), Some(xing));
+    }
+
+    /// Verifies that parse_xing() finds the tag after the side information, whose length depends on the
+    /// MPEG version, the channel mode, and whether a CRC is present.
+    #[test]
+    fn test_parse_xing_side_info_offsets()
+    {
+        let cases: [([u8; 4], usize); 6] = [
+            // MPEG Version 1, Layer III, 128Kbps, 44.1KHz: stereo, mono, and protected mono
+            (STEREO_HEADER, 32),
+            ([0xFF, 0xFB, 0x90, 0xC4], 17),
+            ([0xFF, 0xFA, 0x90, 0xC4], 19),
+            // MPEG Version 2, Layer III, 64Kbps, 22.05KHz: stereo and mono
+            ([0xFF, 0xF3, 0x80, 0x44], 17),
+            ([0xFF, 0xF3, 0x80, 0xC4], 9),
+            // MPEG Version 2.5, Layer III, 32Kbps, 11.025KHz, mono
+            ([0xFF, 0xE3, 0x40, 0xC4], 9),
+        ];
+        for &(head, offset) in cases.iter()
+        {
+            let header = FrameHeader::new(head).unwrap();
+            assert_eq!(side_info_len(&header), offset);
+            let mut data = vec![0u8; header.calc_frame_len() as usize - 4];
+            let tag = XingHeader::new(10, 5_000, [0; 100]).to_bytes();
+            data[offset..offset + tag.len()].copy_from_slice(&tag);
+            assert_eq!(parse_xing(&header, &data).unwrap().frames(), Some(10));
+
+            // The tag isn't found at the offset used by any other kind of frame.
+            let mut shifted = vec![0u8; data.len()];
+            let other = if offset == 32 { 17 } else { 32 };
+            shifted[other..other + tag.len()].copy_from_slice(&tag);
+            assert_eq!(parse_xing(&header, &shifted), None);
+        }
+    }
+
+    /// Verifies that XingHeader::toc_offsets() scales the table of contents by the byte count.
+    #[test]
+    fn test_xing_toc_offsets()
+    {
+        let mut toc = [0u8; 100];
+        for (i, entry) in toc.iter_mut().enumerate()
+        {
+            *entry = (i * 256 / 100) as u8;
+        }
+        let offsets = XingHeader::new(1_000, 512_000, toc).toc_offsets(512_000).unwrap();
+        assert_eq!(offsets[0], 0);
+        assert_eq!(offsets[10], 25 * 2_000);
+        assert_eq!(offsets[50], 128 * 2_000);
+        assert_eq!(offsets[99], 253 * 2_000);
+
+        let header = FrameHeader::new(STEREO_HEADER).unwrap();
+        let mut data = vec![0u8; 413];
+        data[32..36].copy_from_slice(b"Xing");
+        data[36..40].copy_from_slice(&0x01u32.to_be_bytes());
+        assert_eq!(parse_xing(&header, &data).unwrap().toc_offsets(512_000), None);
+    }
+
+    /// Verifies that parse_lame() reads the encoder delay and padding.
+    #[test]
+    fn test_parse_lame()
+    {
+        let header = FrameHeader::new(STEREO_HEADER).unwrap();
+        let mut data = vec![0u8; 413];
+        data[32..36].copy_from_slice(b"Info");
+        data[152..161].copy_from_slice(b"LAME3.100");
+        data[173..176].copy_from_slice(&[0x24, 0x03, 0x8C]);
+        let lame = parse_lame(&header, &data).unwrap();
+        assert_eq!(lame.encoder(), "LAME3.100");
+        assert_eq!(lame.encoder_delay(), 576);
+        assert_eq!(lame.encoder_padding(), 908);
+        assert_eq!(lame.vbr_method(), 0);
+        data[161] = 0x14;
+        assert_eq!(parse_lame(&header, &data).unwrap().vbr_method(), 4);
+
+        // With a shorter encoder string padded by NULs and a space
+        data[152..161].copy_from_slice(b"LAME3.97 ");
+        assert_eq!(parse_lame(&header, &data).unwrap().encoder(), "LAME3.97");
+        data[152..161].copy_from_slice(b"Lavf\0\0\0\0\0");
+        assert_eq!(parse_lame(&header, &data).unwrap().encoder(), "Lavf");
+
+        // Without an encoder string
+        data[152..161].copy_from_slice(&[0; 9]);
+        assert_eq!(parse_lame(&header, &data), None);
+
+        // Without a Xing header
+        data[152..161].copy_from_slice(b"LAME3.100");
+        data[32..36].copy_from_slice(&[0; 4]);
+        assert_eq!(parse_lame(&header, &data), None);
+    }
+
+    /// Verifies that parse_lame() splits the 24 bit delay and padding field at the 12th bit, so that
+    /// neither value leaks into the other.
+    #[test]
+    fn test_parse_lame_delay_padding_bits()
+    {
+        let header = FrameHeader::new(STEREO_HEADER).unwrap();
+        let mut data = vec![0u8; 413];
+        data[32..36].copy_from_slice(b"Xing");
+        data[152..161].copy_from_slice(b"LAME3.99r");
+        for (packed, delay, padding) in [
+            ([0xFF, 0xF0, 0x00], 4095, 0),
+            ([0x00, 0x0F, 0xFF], 0, 4095),
+            ([0x80, 0x08, 0x01], 2048, 2049),
+            // The 576 samples of delay that LAME writes for MPEG Version 1 Layer III
+            ([0x24, 0x06, 0x3C], 576, 1596),
+        ]
+        {
+            data[173..176].copy_from_slice(&packed);
+            let lame = parse_lame(&header, &data).unwrap();
+            assert_eq!((lame.encoder_delay(), lame.encoder_padding()), (delay, padding));
+        }
+    }
+
+    /// Verifies that parse_vbri() reads the fields of a VBRI header.
+    #[test]
+    fn test_parse_vbri()
+    {
+        let header = FrameHeader::new(STEREO_HEADER).unwrap();
+        let mut data = vec![0u8; 413];
+        data[32..36].copy_from_slice(b"VBRI");
+        data[36..38].copy_from_slice(&1u16.to_be_bytes());
+        data[38..40].copy_from_slice(&1_105u16.to_be_bytes());
+        data[40..42].copy_from_slice(&75u16.to_be_bytes());
+        data[42..46].copy_from_slice(&2_000_000u32.to_be_bytes());
+        data[46..50].copy_from_slice(&7_500u32.to_be_bytes());
+
+        let vbri = parse_vbri(&header, &data).unwrap();
+        assert_eq!(vbri.version(), 1);
+        assert_eq!(vbri.delay(), 1_105);
+        assert_eq!(vbri.quality(), 75);
+        assert_eq!(vbri.bytes(), 2_000_000);
+        assert_eq!(vbri.frames(), 7_500);
+        assert!(vbri.seek_table().is_empty());
+        assert_eq!(vbri.seek_offset(0.5), None);
+        assert_eq!(parse_xing(&header, &data), None);
+    }
+
+    /// Verifies that parse_vbri() reads seek tables with 2 and 4 byte entries and that
+    /// VbriHeader::seek_offset() interpolates between the seek points.
+    #[test]
+    fn test_parse_vbri_seek_table()
+    {
+        let header = FrameHeader::new(STEREO_HEADER).unwrap();
+        let mut data = vec![0u8; 413];
+        data[32..36].copy_from_slice(b"VBRI");
+        data[50..52].copy_from_slice(&4u16.to_be_bytes());
+        data[52..54].copy_from_slice(&2u16.to_be_bytes());
+        data[54..56].copy_from_slice(&2u16.to_be_bytes());
+        for (i, size) in [100u16, 300, 200, 400].iter().enumerate()
+        {
+            data[58 + 2 * i..60 + 2 * i].copy_from_slice(&size.to_be_bytes());
+        }
+        let vbri = parse_vbri(&header, &data).unwrap();
+        assert_eq!(vbri.seek_table(), &[0, 200, 800, 1_200, 2_000]);
+        assert_eq!(vbri.seek_offset(0.0), Some(0));
+        assert_eq!(vbri.seek_offset(0.375), Some(500));
+        assert_eq!(vbri.seek_offset(1.0), Some(2_000));
+        assert_eq!(vbri.seek_offset(1.5), None);
+
+        // The same table with 4 byte entries and no scaling
+        data[52..54].copy_from_slice(&1u16.to_be_bytes());
+        data[54..56].copy_from_slice(&4u16.to_be_bytes());
+        for (i, size) in [200u32, 600, 400, 800].iter().enumerate()
+        {
+            data[58 + 4 * i..62 + 4 * i].copy_from_slice(&size.to_be_bytes());
+        }
+        assert_eq!(parse_vbri(&header, &data).unwrap().seek_table(), &[0, 200, 800, 1_200, 2_000]);
+
+        // Entries longer than 4 bytes aren't supported
+        data[54..56].copy_from_slice(&5u16.to_be_bytes());
+        assert!(parse_vbri(&header, &data).unwrap().seek_table().is_empty());
+    }
+}